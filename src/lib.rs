@@ -44,6 +44,8 @@ use digest::{
     FixedOutput, HashMarker, Update,
 };
 use hmac::{Hmac, Mac};
+use std::fmt;
+use std::str::FromStr;
 pub use sha1::Sha1;
 pub use sha2::{Sha256, Sha512};
 
@@ -53,6 +55,64 @@ pub const DEFAULT_STEP: u64 = 30;
 /// 8 digits of output.
 pub const DEFAULT_DIGITS: u32 = 8;
 
+/// The hash algorithm backing a one-time password.
+///
+/// The three SHA variants supported by the RFCs, named as they appear in the
+/// `algorithm=` field of an `otpauth://` URI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// SHA-1, the default assumed by most authenticator apps.
+    Sha1,
+    /// SHA-256.
+    Sha256,
+    /// SHA-512.
+    Sha512,
+}
+
+impl Algorithm {
+    /// The canonical name used in `otpauth://` URIs and `algorithm=` config fields.
+    fn rfc_name(self) -> &'static str {
+        match self {
+            Algorithm::Sha1 => "SHA1",
+            Algorithm::Sha256 => "SHA256",
+            Algorithm::Sha512 => "SHA512",
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.rfc_name())
+    }
+}
+
+/// The error returned when a string does not name a known [`Algorithm`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAlgorithmError(String);
+
+impl fmt::Display for ParseAlgorithmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown hash algorithm: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseAlgorithmError {}
+
+impl FromStr for Algorithm {
+    type Err = ParseAlgorithmError;
+
+    /// Parse an algorithm name case-insensitively, so that the `SHA1` produced by
+    /// [`Display`](std::fmt::Display) round-trips back to [`Algorithm::Sha1`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "SHA1" => Ok(Algorithm::Sha1),
+            "SHA256" => Ok(Algorithm::Sha256),
+            "SHA512" => Ok(Algorithm::Sha512),
+            _ => Err(ParseAlgorithmError(s.to_string())),
+        }
+    }
+}
+
 /// Produce a Time-based One-time Password with default settings.
 ///
 /// ```
@@ -114,9 +174,67 @@ where
     <H::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
     Le<<H::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
 {
-    // Hash the secret and the time together.
+    // A TOTP is just an HOTP whose counter is derived from the current time.
+    hotp_custom::<H>(time / step, digits, secret)
+}
+
+/// Produce a Time-based One-time Password, selecting the hash algorithm at runtime.
+///
+/// The generic functions fix the hash variant at compile time through their `H`
+/// type parameter. When the variant is only known at runtime — parsed from the
+/// `algorithm=` field of an [`otpauth_uri`] or loaded from stored config — pass
+/// an [`Algorithm`] value instead, and this function dispatches to the matching
+/// [`totp_custom`] instantiation.
+///
+/// ```
+/// use totp_lite::{totp_custom, totp_dyn, Algorithm, Sha256, DEFAULT_STEP};
+///
+/// let secret: &[u8] = b"12345678901234567890123456789012";
+/// assert_eq!(
+///     totp_custom::<Sha256>(DEFAULT_STEP, 8, secret, 1234567890),
+///     totp_dyn(Algorithm::Sha256, DEFAULT_STEP, 8, secret, 1234567890),
+/// );
+/// ```
+pub fn totp_dyn(alg: Algorithm, step: u64, digits: u32, secret: &[u8], time: u64) -> String {
+    match alg {
+        Algorithm::Sha1 => totp_custom::<Sha1>(step, digits, secret, time),
+        Algorithm::Sha256 => totp_custom::<Sha256>(step, digits, secret, time),
+        Algorithm::Sha512 => totp_custom::<Sha512>(step, digits, secret, time),
+    }
+}
+
+/// Produce an HMAC-based One-time Password as described by [RFC4226].
+///
+/// This is the counter-based sibling of [`totp_custom`]: the two share the same
+/// dynamic-truncation core, with `totp_custom` supplying `time / step` as the
+/// `counter`. Use it directly for event- or hardware-token flows where the
+/// moving factor is an explicit counter rather than the clock.
+///
+/// ```
+/// use totp_lite::{hotp_custom, Sha1};
+///
+/// // The counter/secret pairs from the RFC4226 test vectors (Appendix D).
+/// let secret: &[u8] = b"12345678901234567890";
+/// assert_eq!("755224", hotp_custom::<Sha1>(0, 6, secret));
+/// assert_eq!("287082", hotp_custom::<Sha1>(1, 6, secret));
+/// ```
+///
+/// [RFC4226]: https://tools.ietf.org/html/rfc4226
+pub fn hotp_custom<H>(counter: u64, digits: u32, secret: &[u8]) -> String
+where
+    H: Update + FixedOutput + CoreProxy,
+    H::Core: HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone,
+    <H::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<H::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+{
+    // Hash the secret and the counter together.
     let mut mac = <Hmac<H> as Mac>::new_from_slice(secret).unwrap();
-    <Hmac<H> as Update>::update(&mut mac, &to_bytes(time / step));
+    <Hmac<H> as Update>::update(&mut mac, &to_bytes(counter));
     let hash: &[u8] = &mac.finalize().into_bytes();
 
     // Magic from the RFC.
@@ -129,6 +247,234 @@ where
     format!("{:01$}", binary % (10_u64.pow(digits)), digits as usize)
 }
 
+/// Verify a candidate Time-based One-time Password, tolerating clock skew.
+///
+/// A client and server rarely agree on the current time to the second, so a
+/// freshly generated code may already have rolled over by the time it reaches
+/// the server. `totp_verify` checks `candidate` not only against the code for
+/// the current counter `time / step`, but against the `skew` counters on either
+/// side of it, returning `true` if any of them match.
+///
+/// Each comparison is performed in constant time: every byte of every candidate
+/// code is examined regardless of where a mismatch occurs, so a caller learns
+/// nothing from the time taken about how many leading digits were correct.
+///
+/// ```
+/// use totp_lite::{totp_custom, totp_verify, Sha1, DEFAULT_STEP};
+///
+/// let secret: &[u8] = b"12345678901234567890";
+///
+/// // A code generated one step in the past is still accepted with a skew of 1.
+/// let code: String = totp_custom::<Sha1>(DEFAULT_STEP, 8, secret, 59);
+/// assert!(totp_verify::<Sha1>(DEFAULT_STEP, 8, 1, secret, 59 + DEFAULT_STEP, &code));
+///
+/// // But not with no tolerance at all.
+/// assert!(!totp_verify::<Sha1>(DEFAULT_STEP, 8, 0, secret, 59 + DEFAULT_STEP, &code));
+/// ```
+pub fn totp_verify<H>(
+    step: u64,
+    digits: u32,
+    skew: u8,
+    secret: &[u8],
+    time: u64,
+    candidate: &str,
+) -> bool
+where
+    H: Update + FixedOutput + CoreProxy,
+    H::Core: HashMarker
+        + UpdateCore
+        + FixedOutputCore
+        + BufferKindUser<BufferKind = Eager>
+        + Default
+        + Clone,
+    <H::Core as BlockSizeUser>::BlockSize: IsLess<U256>,
+    Le<<H::Core as BlockSizeUser>::BlockSize, U256>: NonZero,
+{
+    let counter: u64 = time / step;
+    let skew: u64 = skew as u64;
+    let candidate: &[u8] = candidate.as_bytes();
+
+    // Check every counter in the window without short-circuiting, so that the
+    // number of comparisons performed does not depend on the candidate.
+    let mut matched: bool = false;
+    let mut c: u64 = counter.saturating_sub(skew);
+    let last: u64 = counter.saturating_add(skew);
+    loop {
+        let expected: String = hotp_custom::<H>(c, digits, secret);
+        matched |= constant_time_eq(expected.as_bytes(), candidate);
+        if c == last {
+            break;
+        }
+        c += 1;
+    }
+    matched
+}
+
+/// Compare two byte slices for equality without leaking, through timing, the
+/// position of the first difference.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff: u8 = 0;
+    a.iter().zip(b.iter()).for_each(|(x, y)| diff |= x ^ y);
+    diff == 0
+}
+
+/// Build an `otpauth://` provisioning URI for enrollment in an authenticator app.
+///
+/// Scanning the returned URI as a QR code shares the raw `secret` with apps such
+/// as Google Authenticator or Authy. The secret is RFC4648 base32-encoded
+/// (without padding) and the human-readable `issuer`/`account` fields are
+/// percent-encoded, producing the `otpauth://totp/{issuer}:{account}?...` form
+/// those apps expect.
+///
+/// ```
+/// use totp_lite::{otpauth_uri, Algorithm, DEFAULT_DIGITS, DEFAULT_STEP};
+///
+/// let uri: String = otpauth_uri(
+///     "Example",
+///     "alice@example.com",
+///     b"secret",
+///     Algorithm::Sha1,
+///     DEFAULT_DIGITS,
+///     DEFAULT_STEP,
+/// );
+/// assert_eq!(
+///     "otpauth://totp/Example:alice%40example.com\
+///      ?secret=ONSWG4TFOQ&issuer=Example&algorithm=SHA1&digits=8&period=30",
+///     uri
+/// );
+/// ```
+pub fn otpauth_uri(
+    issuer: &str,
+    account: &str,
+    secret: &[u8],
+    algorithm: Algorithm,
+    digits: u32,
+    step: u64,
+) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm={}&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account),
+        base32_encode(secret),
+        percent_encode(issuer),
+        algorithm.rfc_name(),
+        digits,
+        step
+    )
+}
+
+/// The RFC4648 base32 alphabet.
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Encode bytes as an RFC4648 base32 string, without trailing padding.
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    for chunk in bytes.chunks(5) {
+        // Pack up to five bytes into the high end of a 40-bit group.
+        let mut group: u64 = 0;
+        chunk
+            .iter()
+            .enumerate()
+            .for_each(|(i, b)| group |= (*b as u64) << (32 - 8 * i));
+
+        // Every complete 5 bits of input becomes one output character; a
+        // partial final group emits only as many characters as it fills.
+        let chars = (chunk.len() * 8).div_ceil(5);
+        (0..chars).for_each(|i| {
+            let idx = ((group >> (35 - 5 * i)) & 0x1f) as usize;
+            out.push(BASE32_ALPHABET[idx] as char);
+        });
+    }
+    out
+}
+
+/// Decode an RFC4648 base32 string into its raw bytes.
+///
+/// Decoding is case-insensitive and tolerant of trailing `=` padding, so a key
+/// as typed into an authenticator enrollment screen can be turned into the
+/// `secret` bytes expected by [`totp`] and friends without further massaging.
+///
+/// Available when the `base32` feature is enabled.
+///
+/// ```
+/// use totp_lite::decode_secret;
+///
+/// assert_eq!(b"secret".to_vec(), decode_secret("ONSWG4TFOQ======").unwrap());
+/// assert_eq!(b"secret".to_vec(), decode_secret("onswg4tfoq").unwrap());
+/// assert!(decode_secret("0189").is_err()); // '0' and '1' are not in the alphabet.
+/// ```
+#[cfg(feature = "base32")]
+pub fn decode_secret(s: &str) -> Result<Vec<u8>, DecodeError> {
+    let mut out: Vec<u8> = Vec::with_capacity(s.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits: u32 = 0;
+    for c in s.chars() {
+        if c == '=' {
+            continue;
+        }
+        let value = base32_value(c).ok_or(DecodeError(c))?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(out)
+}
+
+/// Generate a random secret of `len` bytes, returned base32-encoded for display.
+///
+/// The bytes are drawn from the operating system's cryptographically secure
+/// random number generator, making the result suitable as a freshly enrolled
+/// shared secret. The raw bytes can be recovered with [`decode_secret`].
+///
+/// Available when the `base32` feature is enabled.
+#[cfg(feature = "base32")]
+pub fn generate_secret(len: usize) -> String {
+    let mut bytes: Vec<u8> = vec![0; len];
+    getrandom::getrandom(&mut bytes).expect("failed to read from the system CSPRNG");
+    base32_encode(&bytes)
+}
+
+/// The position of a character within the RFC4648 base32 alphabet, if any.
+#[cfg(feature = "base32")]
+fn base32_value(c: char) -> Option<u8> {
+    BASE32_ALPHABET
+        .iter()
+        .position(|&a| a == c.to_ascii_uppercase() as u8)
+        .map(|i| i as u8)
+}
+
+/// The error returned when [`decode_secret`] meets a character outside the
+/// RFC4648 base32 alphabet.
+#[cfg(feature = "base32")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError(char);
+
+#[cfg(feature = "base32")]
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid base32 character: {:?}", self.0)
+    }
+}
+
+#[cfg(feature = "base32")]
+impl std::error::Error for DecodeError {}
+
+/// Percent-encode a string, leaving only the RFC3986 unreserved characters bare.
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    s.bytes().for_each(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => out.push(b as char),
+        _ => out.push_str(&format!("%{:02X}", b)),
+    });
+    out
+}
+
 /// Convert a `u64` into its individual bytes.
 fn to_bytes(n: u64) -> [u8; 8] {
     let mask = 0x00000000000000ff;
@@ -167,6 +513,134 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "base32")]
+    #[test]
+    fn decode_secret_test() {
+        // RFC4648 test vectors, round-tripped against the encoder.
+        assert_eq!(b"".to_vec(), decode_secret("").unwrap());
+        assert_eq!(b"foo".to_vec(), decode_secret("MZXW6").unwrap());
+        assert_eq!(b"foobar".to_vec(), decode_secret("MZXW6YTBOI").unwrap());
+
+        // Padding-tolerant and case-insensitive.
+        assert_eq!(b"foob".to_vec(), decode_secret("MZXW6YQ=").unwrap());
+        assert_eq!(b"foobar".to_vec(), decode_secret("mzxw6ytboi").unwrap());
+
+        // Characters outside the alphabet are rejected.
+        assert!(decode_secret("0189").is_err());
+    }
+
+    #[cfg(feature = "base32")]
+    #[test]
+    fn generate_secret_round_trip() {
+        let secret: String = generate_secret(20);
+        assert_eq!(20, decode_secret(&secret).unwrap().len());
+    }
+
+    #[test]
+    fn algorithm_round_trip() {
+        [Algorithm::Sha1, Algorithm::Sha256, Algorithm::Sha512]
+            .into_iter()
+            .for_each(|alg| {
+                assert_eq!(Ok(alg), alg.to_string().parse());
+            });
+
+        // Parsing is case-insensitive.
+        assert_eq!(Ok(Algorithm::Sha256), "sha256".parse());
+        assert!("SHA3".parse::<Algorithm>().is_err());
+    }
+
+    #[test]
+    fn totp_dyn_matches_generic() {
+        let secret: &[u8] = b"12345678901234567890123456789012";
+        assert_eq!(
+            totp_custom::<Sha256>(DEFAULT_STEP, 8, secret, 1234567890),
+            totp_dyn(Algorithm::Sha256, DEFAULT_STEP, 8, secret, 1234567890)
+        );
+    }
+
+    #[test]
+    fn base32_encode_test() {
+        // RFC4648 test vectors, minus the padding.
+        assert_eq!("", base32_encode(b""));
+        assert_eq!("MY", base32_encode(b"f"));
+        assert_eq!("MZXQ", base32_encode(b"fo"));
+        assert_eq!("MZXW6", base32_encode(b"foo"));
+        assert_eq!("MZXW6YQ", base32_encode(b"foob"));
+        assert_eq!("MZXW6YTB", base32_encode(b"fooba"));
+        assert_eq!("MZXW6YTBOI", base32_encode(b"foobar"));
+    }
+
+    #[test]
+    fn otpauth_uri_test() {
+        assert_eq!(
+            "otpauth://totp/Example:alice%40example.com\
+             ?secret=ONSWG4TFOQ&issuer=Example&algorithm=SHA256&digits=6&period=30",
+            otpauth_uri(
+                "Example",
+                "alice@example.com",
+                b"secret",
+                Algorithm::Sha256,
+                6,
+                30
+            )
+        );
+    }
+
+    #[test]
+    fn hotp_tests() {
+        // The RFC4226 test vectors (Appendix D).
+        let secret: &[u8] = b"12345678901234567890";
+
+        let pairs = vec![
+            ("755224", 0),
+            ("287082", 1),
+            ("359152", 2),
+            ("969429", 3),
+            ("338314", 4),
+            ("254676", 5),
+            ("287922", 6),
+            ("162583", 7),
+            ("399871", 8),
+            ("520489", 9),
+        ];
+
+        pairs.into_iter().for_each(|(expected, counter)| {
+            assert_eq!(expected, hotp_custom::<Sha1>(counter, 6, secret));
+        });
+    }
+
+    #[test]
+    fn verify_within_skew() {
+        let secret: &[u8] = b"12345678901234567890";
+        let code: String = totp_custom::<Sha1>(DEFAULT_STEP, 8, secret, 59);
+
+        // Exact match, no skew needed.
+        assert!(totp_verify::<Sha1>(DEFAULT_STEP, 8, 0, secret, 59, &code));
+
+        // Accepted one step late when a skew of 1 is allowed.
+        assert!(totp_verify::<Sha1>(
+            DEFAULT_STEP,
+            8,
+            1,
+            secret,
+            59 + DEFAULT_STEP,
+            &code
+        ));
+
+        // Rejected two steps late, outside the window.
+        assert!(!totp_verify::<Sha1>(
+            DEFAULT_STEP,
+            8,
+            1,
+            secret,
+            59 + 2 * DEFAULT_STEP,
+            &code
+        ));
+
+        // A wrong code of the right length is rejected.
+        assert!(!totp_verify::<Sha1>(DEFAULT_STEP, 8, 1, secret, 59, "00000000"));
+    }
+
     #[test]
     fn totp1_tests() {
         let secret: &[u8] = b"12345678901234567890";